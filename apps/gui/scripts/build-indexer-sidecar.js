@@ -0,0 +1,32 @@
+#!/usr/bin/env node
+// Builds services/indexer and copies it to src-tauri/binaries under the
+// target-triple name tauri-build expects for `bundle.externalBin`.
+import { execFileSync } from 'node:child_process';
+import { copyFileSync, mkdirSync } from 'node:fs';
+import { dirname, join, resolve } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const guiDir = resolve(dirname(fileURLToPath(import.meta.url)), '..');
+const manifest = resolve(guiDir, '../../services/indexer/Cargo.toml');
+
+// Set by the Tauri CLI for beforeDevCommand/beforeBuildCommand.
+const triple =
+  process.env.TAURI_ENV_TARGET_TRIPLE ||
+  execFileSync('rustc', ['-vV'], { encoding: 'utf8' }).match(/^host: (\S+)$/m)[1];
+const release = process.env.TAURI_ENV_DEBUG !== 'true';
+const exe = triple.includes('windows') ? '.exe' : '';
+
+const args = ['build', '--manifest-path', manifest, '--target', triple];
+if (release) args.push('--release');
+execFileSync('cargo', args, { stdio: 'inherit' });
+
+const built = join(
+  dirname(manifest),
+  'target',
+  triple,
+  release ? 'release' : 'debug',
+  `ossaat-indexer${exe}`
+);
+const binaries = join(guiDir, 'src-tauri', 'binaries');
+mkdirSync(binaries, { recursive: true });
+copyFileSync(built, join(binaries, `ossaat-indexer-${triple}${exe}`));
@@ -0,0 +1,96 @@
+use serde::Serialize;
+
+use crate::error::CommandError;
+
+/// Keychain service name shared by every credential the app stores.
+const SERVICE: &str = "oss-ai-agent-tool";
+
+/// Credentials the webview may manage, keyed like the orchestrator's
+/// SecretsStore, with the environment variable each one used to come from.
+const KNOWN_CREDENTIALS: &[(&str, &str)] = &[
+    ("indexer:apiKey", "INDEXER_API_KEY"),
+    ("provider:openai:apiKey", "OPENAI_API_KEY"),
+    ("provider:anthropic:apiKey", "ANTHROPIC_API_KEY"),
+    ("provider:google:apiKey", "GOOGLE_API_KEY"),
+    ("provider:azureopenai:apiKey", "AZURE_OPENAI_API_KEY"),
+    ("provider:mistral:apiKey", "MISTRAL_API_KEY"),
+    ("provider:openrouter:apiKey", "OPENROUTER_API_KEY"),
+];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigratedCredential {
+    pub key: &'static str,
+    pub env: &'static str,
+}
+
+fn entry(key: &str) -> Result<keyring::Entry, CommandError> {
+    if !KNOWN_CREDENTIALS.iter().any(|(known, _)| *known == key) {
+        return Err(CommandError::Invalid(format!("unknown credential {key:?}")));
+    }
+    Ok(keyring::Entry::new(SERVICE, key)?)
+}
+
+fn read(key: &str) -> Result<Option<String>, CommandError> {
+    match entry(key)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Runs a blocking keychain call off the async runtime's worker threads; the
+/// platform stores can block on D-Bus or user prompts.
+async fn blocking<T, F>(f: F) -> Result<T, CommandError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, CommandError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|err| CommandError::Invalid(format!("keychain task failed: {err}")))?
+}
+
+#[tauri::command]
+pub async fn set_credential(key: String, secret: String) -> Result<(), CommandError> {
+    if secret.is_empty() {
+        return Err(CommandError::Invalid("secret must not be empty".into()));
+    }
+    blocking(move || Ok(entry(&key)?.set_password(&secret)?)).await
+}
+
+#[tauri::command]
+pub async fn get_credential(key: String) -> Result<Option<String>, CommandError> {
+    blocking(move || read(&key)).await
+}
+
+#[tauri::command]
+pub async fn delete_credential(key: String) -> Result<(), CommandError> {
+    blocking(move || match entry(&key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(err.into()),
+    })
+    .await
+}
+
+/// Copies credentials still provided through environment variables into the
+/// keychain, leaving entries that already exist untouched. Returns what was
+/// migrated so the UI can tell the user which variables to remove.
+#[tauri::command]
+pub async fn migrate_env_credentials() -> Result<Vec<MigratedCredential>, CommandError> {
+    blocking(|| {
+        let mut migrated = Vec::new();
+        for &(key, env) in KNOWN_CREDENTIALS {
+            let Some(secret) = std::env::var(env).ok().filter(|value| !value.is_empty()) else {
+                continue;
+            };
+            if read(key)?.is_some() {
+                continue;
+            }
+            entry(key)?.set_password(&secret)?;
+            migrated.push(MigratedCredential { key, env });
+        }
+        Ok(migrated)
+    })
+    .await
+}
@@ -0,0 +1,204 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{App, AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::error::CommandError;
+
+pub const SCHEME: &str = "ossagent";
+/// Event emitted to the webview whenever a link asks to open a file.
+pub const OPEN_FILE_EVENT: &str = "deep-link:open-file";
+
+/// A request to show `path` (relative to the open workspace) at `line`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OpenFileRequest {
+    pub path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// Holds the last request until the webview collects it, so links that arrive
+/// before the frontend subscribes (e.g. the one that launched the app) are
+/// not lost.
+#[derive(Default)]
+pub struct PendingDeepLink(Mutex<Option<OpenFileRequest>>);
+
+/// Parses `ossagent://open?path=src/lib.rs&line=42&column=7`.
+pub fn parse(url: &Url) -> Result<OpenFileRequest, CommandError> {
+    if url.scheme() != SCHEME {
+        return Err(CommandError::Invalid(format!(
+            "unsupported scheme {:?}",
+            url.scheme()
+        )));
+    }
+    if url.host_str() != Some("open") {
+        return Err(CommandError::Invalid(format!(
+            "unsupported link action {:?}",
+            url.host_str().unwrap_or_default()
+        )));
+    }
+
+    let mut path = None;
+    let mut line = None;
+    let mut column = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "path" => path = Some(value.into_owned()),
+            "line" => line = Some(parse_position("line", &value)?),
+            "column" => column = Some(parse_position("column", &value)?),
+            _ => {}
+        }
+    }
+
+    let path = path
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| CommandError::Invalid("link is missing a path".into()))?;
+    if !is_workspace_relative(&path) {
+        return Err(CommandError::Invalid(format!(
+            "path {path:?} must be relative to the workspace"
+        )));
+    }
+
+    Ok(OpenFileRequest { path, line, column })
+}
+
+/// Links come from chat messages and browsers; only allow paths that stay
+/// inside the workspace root. Both separators are checked on every platform so
+/// a link behaves the same wherever it is opened, and `:` is refused to rule
+/// out drive prefixes like `C:foo` and NTFS stream names.
+fn is_workspace_relative(path: &str) -> bool {
+    !path.starts_with(['/', '\\'])
+        && path
+            .split(['/', '\\'])
+            .all(|segment| segment != ".." && !segment.contains(':'))
+}
+
+fn parse_position(name: &str, value: &str) -> Result<u32, CommandError> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| CommandError::Invalid(format!("{name} must be a positive integer")))
+}
+
+/// Hooks link delivery into the app: links received while running and the
+/// link that launched the app are both routed through [`handle_urls`].
+pub fn setup(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
+    // Installed bundles register the scheme themselves; this covers dev runs.
+    #[cfg(any(windows, target_os = "linux"))]
+    app.deep_link().register_all()?;
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_urls(&handle, event.urls());
+    });
+
+    if let Some(urls) = app.deep_link().get_current()? {
+        handle_urls(app.handle(), urls);
+    }
+    Ok(())
+}
+
+fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    let Some(request) = urls.iter().rev().find_map(|url| match parse(url) {
+        Ok(request) => Some(request),
+        Err(err) => {
+            log::warn!("ignoring deep link {url}: {err}");
+            None
+        }
+    }) else {
+        return;
+    };
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    *app.state::<PendingDeepLink>()
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(request.clone());
+    if let Err(err) = app.emit(OPEN_FILE_EVENT, request) {
+        log::warn!("failed to forward deep link to the webview: {err}");
+    }
+}
+
+/// Returns and clears the most recent link request, if any.
+#[tauri::command]
+pub fn take_pending_deep_link(pending: State<'_, PendingDeepLink>) -> Option<OpenFileRequest> {
+    pending
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(url: &str) -> Result<OpenFileRequest, CommandError> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    fn rejects(url: &str) {
+        assert!(
+            matches!(parse_str(url), Err(CommandError::Invalid(_))),
+            "{url} should be rejected"
+        );
+    }
+
+    #[test]
+    fn parses_path_line_and_column() {
+        assert_eq!(
+            parse_str("ossagent://open?path=src/lib.rs&line=42&column=7").unwrap(),
+            OpenFileRequest {
+                path: "src/lib.rs".into(),
+                line: Some(42),
+                column: Some(7),
+            }
+        );
+        let request = parse_str("ossagent://open?path=./docs/a%20b.md").unwrap();
+        assert_eq!(request.path, "./docs/a b.md");
+        assert_eq!(request.line, None);
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_workspace() {
+        rejects("ossagent://open?path=/etc/passwd");
+        rejects("ossagent://open?path=%5CWindows%5Csystem32");
+        rejects("ossagent://open?path=../secrets");
+        rejects("ossagent://open?path=src/../../secrets");
+        rejects("ossagent://open?path=src/%2E%2E/%2E%2E/secrets");
+        rejects("ossagent://open?path=src%5C..%5C..%5Csecrets");
+        rejects("ossagent://open?path=C:foo");
+        rejects("ossagent://open?path=C:%5CUsers");
+        rejects("ossagent://open?path=file.txt:stream");
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_actions() {
+        rejects("https://open?path=src/lib.rs");
+        rejects("ossagent://run?path=src/lib.rs");
+        rejects("ossagent:open?path=src/lib.rs");
+    }
+
+    #[test]
+    fn rejects_missing_or_empty_path() {
+        rejects("ossagent://open");
+        rejects("ossagent://open?line=3");
+        rejects("ossagent://open?path=");
+    }
+
+    #[test]
+    fn rejects_invalid_positions() {
+        rejects("ossagent://open?path=a.rs&line=0");
+        rejects("ossagent://open?path=a.rs&column=0");
+        rejects("ossagent://open?path=a.rs&line=abc");
+        rejects("ossagent://open?path=a.rs&line=-1");
+        rejects("ossagent://open?path=a.rs&line=99999999999");
+    }
+}
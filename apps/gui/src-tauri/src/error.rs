@@ -0,0 +1,26 @@
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+/// Error returned from Tauri commands; serialized to the webview as a plain
+/// message string.
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("keychain error: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("malformed data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    #[error("update failed: {0}")]
+    Updater(#[from] tauri_plugin_updater::Error),
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
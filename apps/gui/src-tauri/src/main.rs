@@ -1,7 +1,15 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod credentials;
+mod deep_link;
+mod error;
+mod sidecar;
+mod updater;
+mod workspaces;
+
 #[cfg(target_os = "linux")]
 use glib::MainContext;
+use tauri::Manager;
 
 fn main() {
     // Ensure we eagerly initialize the GLib main context so that async helpers can
@@ -9,7 +17,50 @@ fn main() {
     #[cfg(target_os = "linux")]
     let _default_context = MainContext::default();
 
-    tauri::Builder::default()
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+    let context = tauri::generate_context!();
+    let mut builder = tauri::Builder::default()
+        // Must be registered first so a second launch (e.g. from an ossagent://
+        // link on Windows/Linux) is forwarded to the running instance.
+        .plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}))
+        .plugin(tauri_plugin_log::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_shell::init());
+    // The plugin fails to start without `plugins.updater` in the config.
+    if updater::is_configured(context.config()) {
+        builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
+    }
+
+    builder
+        .manage(workspaces::WorkspaceStore::default())
+        .manage(deep_link::PendingDeepLink::default())
+        .manage(sidecar::Sidecar::default())
+        .setup(|app| {
+            sidecar::start(app.handle());
+            deep_link::setup(app)
+        })
+        .invoke_handler(tauri::generate_handler![
+            credentials::set_credential,
+            credentials::get_credential,
+            credentials::delete_credential,
+            credentials::migrate_env_credentials,
+            deep_link::take_pending_deep_link,
+            sidecar::indexer_logs,
+            sidecar::indexer_status,
+            sidecar::restart_indexer,
+            updater::get_update_channel,
+            updater::set_update_channel,
+            updater::check_for_update,
+            updater::install_update,
+            workspaces::open_workspace,
+            workspaces::list_recent_workspaces,
+            workspaces::remove_workspace,
+        ])
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<sidecar::Sidecar>().shutdown();
+            }
+        });
 }
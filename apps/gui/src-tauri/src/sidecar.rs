@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::error::CommandError;
+
+/// Name of the bundled binary (`bundle.externalBin` in tauri.conf.json).
+const SIDECAR: &str = "ossaat-indexer";
+const PREFERRED_PORT: u16 = 7070;
+const LOG_FILE: &str = "indexer.log";
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_BUFFERED_LINES: usize = 1000;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A run that lasted this long counts as healthy and resets the backoff.
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+/// Supervision state for the bundled indexer process.
+#[derive(Default)]
+pub struct Sidecar {
+    child: Mutex<Option<CommandChild>>,
+    url: Mutex<Option<String>>,
+    logs: Mutex<VecDeque<String>>,
+    restarts: AtomicU32,
+    restart_requested: AtomicBool,
+    shutting_down: AtomicBool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexerStatus {
+    running: bool,
+    url: Option<String>,
+    restarts: u32,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Sidecar {
+    fn push_log(&self, file: &mut Option<LogFile>, line: String) {
+        if let Some(out) = file.as_mut() {
+            // Log capture is best effort; a full disk must not kill supervision.
+            let _ = out.write_line(&line);
+        }
+        let mut logs = lock(&self.logs);
+        if logs.len() == MAX_BUFFERED_LINES {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+
+    /// Stops the running process, if any. Called on app exit.
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        if let Some(child) = lock(&self.child).take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Starts the supervisor loop: spawn the indexer on a free loopback port,
+/// capture its output, and restart it with exponential backoff when it dies.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let sidecar = app.state::<Sidecar>();
+        let mut backoff = INITIAL_BACKOFF;
+        let mut log_file = None;
+
+        while !sidecar.shutting_down.load(Ordering::SeqCst) {
+            let started = Instant::now();
+            if let Err(err) = run_once(&app, &sidecar, &mut log_file).await {
+                sidecar.push_log(&mut log_file, format!("[supervisor] {err}"));
+            }
+            *lock(&sidecar.url) = None;
+
+            if sidecar.shutting_down.load(Ordering::SeqCst) {
+                break;
+            }
+            sidecar.restarts.fetch_add(1, Ordering::SeqCst);
+            if sidecar.restart_requested.swap(false, Ordering::SeqCst) {
+                backoff = INITIAL_BACKOFF;
+                continue;
+            }
+            if started.elapsed() >= STABLE_RUN {
+                backoff = INITIAL_BACKOFF;
+            }
+            sidecar.push_log(
+                &mut log_file,
+                format!("[supervisor] restarting in {}s", backoff.as_secs()),
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+}
+
+async fn run_once(
+    app: &AppHandle,
+    sidecar: &Sidecar,
+    log_file: &mut Option<LogFile>,
+) -> Result<(), CommandError> {
+    if log_file.is_none() {
+        *log_file = app
+            .path()
+            .app_log_dir()
+            .ok()
+            .and_then(|dir| LogFile::open(dir.join(LOG_FILE)).ok());
+    }
+    let port = pick_port()?;
+    let addr = format!("127.0.0.1:{port}");
+
+    // Spawn and store under the child lock. `shutdown` sets the flag before
+    // taking the lock, so it either stops us spawning or finds the child to
+    // kill. The shell plugin only kills children spawned from JS on exit, so
+    // a child it missed would outlive the app and keep holding the port.
+    let mut events = {
+        let mut slot = lock(&sidecar.child);
+        if sidecar.shutting_down.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let (events, child) = app
+            .shell()
+            .sidecar(SIDECAR)
+            .map_err(|err| CommandError::Invalid(format!("indexer sidecar unavailable: {err}")))?
+            .env("INDEXER_HTTP_ADDR", &addr)
+            .spawn()
+            .map_err(|err| CommandError::Invalid(format!("failed to start indexer: {err}")))?;
+        sidecar.push_log(
+            log_file,
+            format!("[supervisor] started indexer pid {} on {addr}", child.pid()),
+        );
+        *slot = Some(child);
+        events
+    };
+    *lock(&sidecar.url) = Some(format!("http://{addr}"));
+
+    while let Some(event) = events.recv().await {
+        match event {
+            CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
+                let line = String::from_utf8_lossy(&line).trim_end().to_owned();
+                sidecar.push_log(log_file, line);
+            }
+            CommandEvent::Error(err) => {
+                sidecar.push_log(log_file, format!("[supervisor] {err}"));
+            }
+            CommandEvent::Terminated(status) => {
+                sidecar.push_log(
+                    log_file,
+                    format!(
+                        "[supervisor] indexer exited (code {:?}, signal {:?})",
+                        status.code, status.signal
+                    ),
+                );
+                break;
+            }
+            _ => {}
+        }
+    }
+    lock(&sidecar.child).take();
+    Ok(())
+}
+
+/// Prefers the indexer's usual port so external tools find it, falling back
+/// to an ephemeral one when something else already holds it. The probe socket
+/// is released before the indexer binds, so a racing process can still win;
+/// the supervisor then simply retries with a fresh port.
+fn pick_port() -> Result<u16, CommandError> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, PREFERRED_PORT))
+        .or_else(|_| TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Captured indexer output, rotated to `<path>.1` once it passes
+/// `MAX_LOG_FILE_BYTES` so a long-running indexer cannot fill the disk.
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.written >= MAX_LOG_FILE_BYTES {
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(".1");
+            std::fs::rename(&self.path, rotated)?;
+            *self = Self::open(self.path.clone())?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+/// Returns the most recent indexer output lines, oldest first.
+#[tauri::command]
+pub fn indexer_logs(sidecar: State<'_, Sidecar>, limit: Option<usize>) -> Vec<String> {
+    let logs = lock(&sidecar.logs);
+    let skip = limit.map_or(0, |limit| logs.len().saturating_sub(limit));
+    logs.iter().skip(skip).cloned().collect()
+}
+
+/// Kills the indexer; the supervisor starts a fresh one without backoff.
+#[tauri::command]
+pub fn restart_indexer(sidecar: State<'_, Sidecar>) -> Result<(), CommandError> {
+    let Some(child) = lock(&sidecar.child).take() else {
+        return Err(CommandError::Invalid("indexer is not running".into()));
+    };
+    sidecar.restart_requested.store(true, Ordering::SeqCst);
+    child
+        .kill()
+        .map_err(|err| CommandError::Invalid(format!("failed to stop indexer: {err}")))
+}
+
+#[tauri::command]
+pub fn indexer_status(sidecar: State<'_, Sidecar>) -> IndexerStatus {
+    IndexerStatus {
+        running: lock(&sidecar.child).is_some(),
+        url: lock(&sidecar.url).clone(),
+        restarts: sidecar.restarts.load(Ordering::SeqCst),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_file_rotates_while_running() {
+        let dir = std::env::temp_dir().join(format!("gui-sidecar-log-{}", std::process::id()));
+        let path = dir.join(LOG_FILE);
+        let mut log = LogFile::open(path.clone()).unwrap();
+        log.written = MAX_LOG_FILE_BYTES - 1;
+
+        log.write_line("last line before rotation").unwrap();
+        log.write_line("first line after rotation").unwrap();
+
+        let read = |path: &std::path::Path| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(&path), "first line after rotation\n");
+        assert_eq!(
+            read(&dir.join("indexer.log.1")),
+            "last line before rotation\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Config, Emitter, Manager, Url};
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::error::CommandError;
+
+const CHANNEL_FILE: &str = "update-channel.json";
+/// Event emitted while an update downloads, carrying `{ downloaded, total }`.
+pub const PROGRESS_EVENT: &str = "updater:progress";
+
+/// Whether this build carries an updater public key. Only release builds merge
+/// `tauri.release.conf.json`, which holds `plugins.updater`; other builds skip
+/// the updater plugin and refuse to install updates.
+pub fn is_configured(config: &Config) -> bool {
+    config
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .is_some_and(|pubkey| !pubkey.is_empty())
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    fn endpoint(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => {
+                "https://github.com/JudgeZ/OSS-AI-Agent-Tool/releases/download/gui-stable/latest.json"
+            }
+            UpdateChannel::Beta => {
+                "https://github.com/JudgeZ/OSS-AI-Agent-Tool/releases/download/gui-beta/latest.json"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub channel: UpdateChannel,
+    pub version: String,
+    pub current_version: String,
+    pub date: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Progress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+fn channel_path(app: &AppHandle) -> Result<PathBuf, CommandError> {
+    Ok(app.path().app_config_dir()?.join(CHANNEL_FILE))
+}
+
+fn read_channel(app: &AppHandle) -> Result<UpdateChannel, CommandError> {
+    match std::fs::read(channel_path(app)?) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(UpdateChannel::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn check(app: &AppHandle) -> Result<Option<tauri_plugin_updater::Update>, CommandError> {
+    if !is_configured(app.config()) {
+        return Err(CommandError::Invalid(
+            "this build is not signed for automatic updates".into(),
+        ));
+    }
+    let endpoint = Url::parse(read_channel(app)?.endpoint())
+        .map_err(|err| CommandError::Invalid(format!("bad update endpoint: {err}")))?;
+
+    let updater = app.updater_builder().endpoints(vec![endpoint])?.build()?;
+    Ok(updater.check().await?)
+}
+
+#[tauri::command]
+pub fn get_update_channel(app: AppHandle) -> Result<UpdateChannel, CommandError> {
+    read_channel(&app)
+}
+
+#[tauri::command]
+pub fn set_update_channel(app: AppHandle, channel: UpdateChannel) -> Result<(), CommandError> {
+    let file = channel_path(&app)?;
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(file, serde_json::to_vec(&channel)?)?;
+    Ok(())
+}
+
+/// Checks the selected channel for a newer, signed release.
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> Result<Option<UpdateInfo>, CommandError> {
+    let channel = read_channel(&app)?;
+    Ok(check(&app).await?.map(|update| UpdateInfo {
+        channel,
+        version: update.version,
+        current_version: update.current_version,
+        date: update.date.map(|date| date.to_string()),
+        notes: update.body,
+    }))
+}
+
+/// Downloads, verifies and installs the pending update, then restarts the
+/// app. Resolves to `false` if there was nothing to install.
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<bool, CommandError> {
+    let Some(update) = check(&app).await? else {
+        return Ok(false);
+    };
+
+    let mut downloaded = 0u64;
+    update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit(PROGRESS_EVENT, Progress { downloaded, total });
+            },
+            || {},
+        )
+        .await?;
+
+    // Windows hands over to the installer and exits before reaching this point.
+    app.restart();
+}
@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+use tokio::sync::oneshot;
+
+use crate::error::CommandError;
+
+const RECENTS_FILE: &str = "recent-workspaces.json";
+const MAX_RECENTS: usize = 10;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentWorkspace {
+    pub path: String,
+    pub name: String,
+    pub last_opened: u64,
+}
+
+/// Serializes read-modify-write cycles on the recents file.
+#[derive(Default)]
+pub struct WorkspaceStore {
+    lock: Mutex<()>,
+}
+
+impl WorkspaceStore {
+    fn guard(&self) -> MutexGuard<'_, ()> {
+        self.lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Shows the native folder picker and records the chosen root as the most
+/// recent workspace. Resolves to `None` when the user cancels the dialog.
+#[tauri::command]
+pub async fn open_workspace(
+    app: AppHandle,
+    store: State<'_, WorkspaceStore>,
+) -> Result<Option<RecentWorkspace>, CommandError> {
+    // The callback form keeps the dialog off the async runtime's worker
+    // threads; the blocking variant would park one until the user answers.
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Open workspace")
+        .pick_folder(move |picked| {
+            let _ = tx.send(picked);
+        });
+    let Ok(Some(picked)) = rx.await else {
+        return Ok(None);
+    };
+    let root = picked
+        .into_path()
+        .map_err(|err| CommandError::Invalid(format!("unsupported folder location: {err}")))?;
+    if !root.is_dir() {
+        return Err(CommandError::Invalid(format!(
+            "{} is not a directory",
+            root.display()
+        )));
+    }
+
+    let workspace = RecentWorkspace {
+        name: display_name(&root),
+        path: root.to_string_lossy().into_owned(),
+        last_opened: unix_now(),
+    };
+
+    let _guard = store.guard();
+    let file = recents_path(&app)?;
+    let mut recents = read_recents(&file)?;
+    remember(&mut recents, workspace.clone());
+    write_recents(&file, &recents)?;
+
+    Ok(Some(workspace))
+}
+
+#[tauri::command]
+pub fn list_recent_workspaces(
+    app: AppHandle,
+    store: State<'_, WorkspaceStore>,
+) -> Result<Vec<RecentWorkspace>, CommandError> {
+    let _guard = store.guard();
+    read_recents(&recents_path(&app)?)
+}
+
+/// Forgets a workspace from the recents list; the folder itself is untouched.
+#[tauri::command]
+pub fn remove_workspace(
+    app: AppHandle,
+    store: State<'_, WorkspaceStore>,
+    path: String,
+) -> Result<Vec<RecentWorkspace>, CommandError> {
+    let _guard = store.guard();
+    let file = recents_path(&app)?;
+    let mut recents = read_recents(&file)?;
+    recents.retain(|existing| existing.path != path);
+    write_recents(&file, &recents)?;
+    Ok(recents)
+}
+
+/// Moves `workspace` to the front of `recents`, dropping any older entry for
+/// the same path and anything beyond `MAX_RECENTS`.
+fn remember(recents: &mut Vec<RecentWorkspace>, workspace: RecentWorkspace) {
+    recents.retain(|existing| existing.path != workspace.path);
+    recents.insert(0, workspace);
+    recents.truncate(MAX_RECENTS);
+}
+
+fn recents_path(app: &AppHandle) -> Result<PathBuf, CommandError> {
+    Ok(app.path().app_data_dir()?.join(RECENTS_FILE))
+}
+
+fn read_recents(file: &Path) -> Result<Vec<RecentWorkspace>, CommandError> {
+    match std::fs::read(file) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn write_recents(file: &Path, recents: &[RecentWorkspace]) -> Result<(), CommandError> {
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // Write-then-rename so a crash never leaves a truncated recents file.
+    let tmp = file.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(recents)?)?;
+    std::fs::rename(&tmp, file)?;
+    Ok(())
+}
+
+fn display_name(root: &Path) -> String {
+    root.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.to_string_lossy().into_owned())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(path: &str, last_opened: u64) -> RecentWorkspace {
+        RecentWorkspace {
+            path: path.into(),
+            name: display_name(Path::new(path)),
+            last_opened,
+        }
+    }
+
+    fn paths(recents: &[RecentWorkspace]) -> Vec<&str> {
+        recents.iter().map(|w| w.path.as_str()).collect()
+    }
+
+    #[test]
+    fn remember_moves_reopened_workspace_to_front() {
+        let mut recents = vec![workspace("/a", 3), workspace("/b", 2), workspace("/c", 1)];
+        remember(&mut recents, workspace("/b", 4));
+        assert_eq!(paths(&recents), ["/b", "/a", "/c"]);
+        assert_eq!(recents[0].last_opened, 4);
+    }
+
+    #[test]
+    fn remember_caps_the_list() {
+        let mut recents: Vec<_> = (0..MAX_RECENTS as u64)
+            .map(|i| workspace(&format!("/old{i}"), i))
+            .collect();
+        remember(&mut recents, workspace("/new", 100));
+        assert_eq!(recents.len(), MAX_RECENTS);
+        assert_eq!(recents[0].path, "/new");
+        assert_eq!(
+            recents[MAX_RECENTS - 1].path,
+            format!("/old{}", MAX_RECENTS - 2)
+        );
+    }
+}
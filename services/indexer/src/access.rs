@@ -0,0 +1,137 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ipnet::IpNet;
+use tracing::warn;
+
+use crate::{error::ApiError, AppState, IndexerError};
+
+/// Client networks allowed to reach the HTTP API. An empty list allows every
+/// peer, which is only sensible while the listener is bound to loopback.
+#[derive(Clone, Debug, Default)]
+pub struct AllowList {
+    networks: Vec<IpNet>,
+}
+
+impl AllowList {
+    /// Reads comma-separated CIDRs (or bare addresses) from
+    /// `INDEXER_HTTP_ALLOW_CIDRS`.
+    pub fn from_env() -> Result<Self, IndexerError> {
+        match std::env::var("INDEXER_HTTP_ALLOW_CIDRS") {
+            Ok(raw) => Self::parse(&raw)
+                .map_err(|err| IndexerError::Config(format!("INDEXER_HTTP_ALLOW_CIDRS: {err}"))),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let networks = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| format!("invalid CIDR or address {entry:?}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { networks })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        // IPv4 clients of a dual-stack listener show up as ::ffff:a.b.c.d.
+        let ip = ip.to_canonical();
+        self.is_empty() || self.networks.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// Rejects requests whose peer address is outside the configured allowlist.
+pub async fn enforce_allow_list(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if state.allow_list.is_empty() {
+        return next.run(req).await;
+    }
+
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    match peer {
+        Some(ip) if state.allow_list.permits(ip) => next.run(req).await,
+        _ => {
+            warn!(peer = ?peer, path = %req.uri().path(), "rejected request from disallowed address");
+            ApiError::Forbidden("client address is not allowed".into()).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn app(allow_list: AllowList) -> Router {
+        let state = AppState {
+            allow_list: Arc::new(allow_list),
+            ..AppState::default()
+        };
+        let router = Router::new().route("/ping", get(|| async { "pong" }));
+        crate::middleware::apply(router, &state).with_state(state)
+    }
+
+    async fn status_from(app: Router, peer: &str) -> StatusCode {
+        let mut req = Request::get("/ping").body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        app.oneshot(req).await.unwrap().status()
+    }
+
+    #[test]
+    fn parse_accepts_cidrs_and_bare_addresses() {
+        let list = AllowList::parse("10.0.0.0/8, 192.168.1.7,::1").unwrap();
+        assert!(list.permits("10.2.3.4".parse().unwrap()));
+        assert!(list.permits("192.168.1.7".parse().unwrap()));
+        assert!(!list.permits("192.168.1.8".parse().unwrap()));
+        assert!(list.permits("::1".parse().unwrap()));
+        assert!(list.permits("::ffff:10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert!(AllowList::parse("10.0.0.0/33").is_err());
+        assert!(AllowList::parse("localhost").is_err());
+    }
+
+    #[tokio::test]
+    async fn empty_list_allows_everyone() {
+        let status = status_from(app(AllowList::default()), "203.0.113.9:5000").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn peers_outside_the_list_are_forbidden() {
+        let list = AllowList::parse("127.0.0.0/8").unwrap();
+        assert_eq!(
+            status_from(app(list.clone()), "127.0.0.1:5000").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_from(app(list), "203.0.113.9:5000").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
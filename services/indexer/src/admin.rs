@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ApiError, AppState};
+
+/// Reads the bearer token that guards every `/admin/*` endpoint from
+/// `INDEXER_ADMIN_TOKEN`. Without it those endpoints are disabled.
+pub fn token_from_env() -> Option<String> {
+    std::env::var("INDEXER_ADMIN_TOKEN")
+        .ok()
+        .map(|token| token.trim().to_owned())
+        .filter(|token| !token.is_empty())
+}
+
+/// Guards every `/admin/*` route. It runs as a route layer, so the token is
+/// checked before any extractor looks at the request body.
+pub async fn require_admin(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    match authorize(&state, req.headers()) {
+        Ok(()) => next.run(req).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let expected = state.admin_token.as_deref().ok_or_else(|| {
+        ApiError::Unavailable("admin endpoints are disabled; set INDEXER_ADMIN_TOKEN".into())
+    })?;
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if constant_time_eq(presented.as_bytes(), expected.as_bytes()) {
+        Ok(())
+    } else {
+        Err(ApiError::Unauthorized(
+            "missing or invalid admin token".into(),
+        ))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogLevelRequest {
+    pub filter: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogLevelResponse {
+    pub filter: String,
+}
+
+pub async fn get_log_level(
+    State(state): State<AppState>,
+) -> Result<Json<LogLevelResponse>, ApiError> {
+    let filter = state
+        .log_level
+        .current()
+        .ok_or_else(|| ApiError::Unavailable("log level is not adjustable".into()))?;
+    Ok(Json(LogLevelResponse { filter }))
+}
+
+/// Replaces the tracing filter, using the same directive syntax as `RUST_LOG`.
+/// The change lasts until the process restarts.
+pub async fn set_log_level(
+    State(state): State<AppState>,
+    Json(req): Json<LogLevelRequest>,
+) -> Result<Json<LogLevelResponse>, ApiError> {
+    if state.log_level.current().is_none() {
+        return Err(ApiError::Unavailable("log level is not adjustable".into()));
+    }
+    let filter = state
+        .log_level
+        .set(&req.filter)
+        .map_err(|err| ApiError::BadRequest(format!("invalid filter: {err}")))?;
+    tracing::warn!(%filter, "log filter changed at runtime");
+    Ok(Json(LogLevelResponse { filter }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn state_with_token() -> AppState {
+        AppState {
+            admin_token: Some("s3cret".into()),
+            ..AppState::default()
+        }
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn admin_endpoints_need_a_configured_and_matching_token() {
+        assert!(matches!(
+            authorize(&AppState::default(), &bearer("s3cret")),
+            Err(ApiError::Unavailable(_))
+        ));
+
+        let state = state_with_token();
+        assert!(authorize(&state, &bearer("s3cret")).is_ok());
+        assert!(matches!(
+            authorize(&state, &bearer("s3cre")),
+            Err(ApiError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            authorize(&state, &HeaderMap::new()),
+            Err(ApiError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn filters_are_validated_and_reloaded() {
+        use tracing_subscriber::{layer::SubscriberExt, reload, EnvFilter};
+
+        let (filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(filter);
+        let log_level = crate::logging::LogLevel::from_handle(handle);
+
+        assert_eq!(log_level.current().as_deref(), Some("info"));
+        assert!(log_level.set("ossaat_indexer=[").is_err());
+        assert_eq!(
+            log_level.set("warn,ossaat_indexer=debug").unwrap(),
+            log_level.current().unwrap()
+        );
+        assert!(log_level
+            .current()
+            .unwrap()
+            .contains("ossaat_indexer=debug"));
+    }
+}
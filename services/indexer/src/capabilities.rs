@@ -0,0 +1,98 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::{format, AppState};
+
+/// What this indexer build and configuration can do, served by
+/// `GET /capabilities` so clients can adapt instead of probing.
+#[derive(Debug, Serialize)]
+pub struct CapabilitiesResponse {
+    version: &'static str,
+    languages: Vec<Language>,
+    features: Features,
+    limits: Limits,
+}
+
+#[derive(Debug, Serialize)]
+struct Language {
+    name: &'static str,
+    /// Version of the bundled parser grammar; `None` while the indexer has no
+    /// parsers.
+    grammar_version: Option<&'static str>,
+    formatter: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Features {
+    format: bool,
+    allow_list: bool,
+    ann_backend: Option<&'static str>,
+    embedding_provider: Option<&'static str>,
+    persistence: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Limits {
+    format_max_source_bytes: usize,
+    format_max_output_bytes: usize,
+    format_timeout_ms: u64,
+}
+
+pub async fn capabilities(State(state): State<AppState>) -> Json<CapabilitiesResponse> {
+    // Only languages something can actually handle are listed. There are no
+    // grammars yet, so that is the formatter set while formatting is enabled.
+    let languages = format::LANGUAGES
+        .into_iter()
+        .filter(|_| state.format.enabled)
+        .filter_map(|name| {
+            Some(Language {
+                name,
+                grammar_version: None,
+                formatter: Some(format::formatter_name(name)?),
+            })
+        })
+        .collect();
+
+    Json(CapabilitiesResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        languages,
+        features: Features {
+            format: state.format.enabled,
+            allow_list: !state.allow_list.is_empty(),
+            ann_backend: None,
+            embedding_provider: None,
+            persistence: None,
+        },
+        limits: Limits {
+            format_max_source_bytes: format::MAX_SOURCE_BYTES,
+            format_max_output_bytes: format::MAX_OUTPUT_BYTES,
+            format_timeout_ms: u64::try_from(state.format.timeout.as_millis()).unwrap_or(u64::MAX),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::FormatConfig;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn languages_are_only_advertised_when_something_handles_them() {
+        let Json(disabled) = capabilities(State(AppState::default())).await;
+        assert!(!disabled.features.format);
+        assert!(disabled.languages.is_empty());
+
+        let state = AppState {
+            format: Arc::new(FormatConfig {
+                enabled: true,
+                ..FormatConfig::default()
+            }),
+            ..AppState::default()
+        };
+        let Json(enabled) = capabilities(State(state)).await;
+        assert!(enabled.features.format);
+        let rust = enabled.languages.iter().find(|l| l.name == "rust").unwrap();
+        assert_eq!(rust.formatter, Some("rustfmt"));
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::AppState;
+
+const MAX_RECENT_ERRORS: usize = 50;
+const MASKED: &str = "********";
+const SECRET_MARKERS: [&str; 5] = ["SECRET", "TOKEN", "KEY", "PASSWORD", "PASSPHRASE"];
+
+/// Process-wide bookkeeping surfaced by `GET /admin/diagnostics`.
+#[derive(Debug)]
+pub struct Diagnostics {
+    started_at: Instant,
+    recent_errors: Mutex<VecDeque<RecordedError>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RecordedError {
+    pub timestamp: u64,
+    pub request_id: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostics {
+    /// Remembers an error for the diagnostics report, keeping only the most
+    /// recent entries.
+    pub fn record_error(&self, request_id: Option<String>, message: impl Into<String>) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let mut errors = self
+            .recent_errors
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if errors.len() == MAX_RECENT_ERRORS {
+            errors.pop_front();
+        }
+        errors.push_back(RecordedError {
+            timestamp,
+            request_id,
+            message: message.into(),
+        });
+    }
+
+    pub fn recent_errors(&self) -> Vec<RecordedError> {
+        self.recent_errors
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            recent_errors: Mutex::new(VecDeque::with_capacity(MAX_RECENT_ERRORS)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsResponse {
+    build: BuildInfo,
+    uptime_secs: u64,
+    http_panics_total: u64,
+    recent_errors: Vec<RecordedError>,
+    environment: Vec<EnvEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct BuildInfo {
+    name: &'static str,
+    version: &'static str,
+    git_sha: Option<&'static str>,
+    profile: &'static str,
+    target_os: &'static str,
+    target_arch: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct EnvEntry {
+    name: String,
+    value: String,
+}
+
+/// Served under `/admin`, so it requires the admin token: the report includes
+/// the indexer's environment and error messages, which can quote request
+/// content.
+pub async fn diagnostics(State(state): State<AppState>) -> Json<DiagnosticsResponse> {
+    Json(DiagnosticsResponse {
+        build: BuildInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: option_env!("GIT_SHA"),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            },
+            target_os: std::env::consts::OS,
+            target_arch: std::env::consts::ARCH,
+        },
+        uptime_secs: state.diagnostics.started_at.elapsed().as_secs(),
+        http_panics_total: state.metrics.http_panics.get(),
+        recent_errors: state.diagnostics.recent_errors(),
+        environment: environment_summary(std::env::vars()),
+    })
+}
+
+/// Lists the variables that influence the indexer, masking anything that
+/// looks like a credential.
+fn environment_summary(vars: impl Iterator<Item = (String, String)>) -> Vec<EnvEntry> {
+    let mut entries: Vec<EnvEntry> = vars
+        .filter(|(name, _)| name.starts_with("INDEXER_") || name == "RUST_LOG")
+        .map(|(name, value)| {
+            let upper = name.to_ascii_uppercase();
+            let value = if SECRET_MARKERS.iter().any(|marker| upper.contains(marker)) {
+                MASKED.to_owned()
+            } else {
+                value
+            };
+            EnvEntry { name, value }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_summary_masks_secrets_and_skips_unrelated_vars() {
+        let vars = [
+            ("INDEXER_API_TOKEN", "hunter2"),
+            ("RUST_LOG", "debug"),
+            ("HOME", "/root"),
+            ("INDEXER_SHUTDOWN_GRACE_SECS", "10"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v.to_owned()));
+
+        let summary = environment_summary(vars);
+        let pairs: Vec<(&str, &str)> = summary
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("INDEXER_API_TOKEN", MASKED),
+                ("INDEXER_SHUTDOWN_GRACE_SECS", "10"),
+                ("RUST_LOG", "debug"),
+            ]
+        );
+    }
+
+    #[test]
+    fn recent_errors_are_bounded_and_newest_first() {
+        let diagnostics = Diagnostics::default();
+        for i in 0..(MAX_RECENT_ERRORS + 5) {
+            diagnostics.record_error(None, format!("error {i}"));
+        }
+
+        let errors = diagnostics.recent_errors();
+        assert_eq!(errors.len(), MAX_RECENT_ERRORS);
+        assert_eq!(
+            errors[0].message,
+            format!("error {}", MAX_RECENT_ERRORS + 4)
+        );
+    }
+}
@@ -0,0 +1,444 @@
+use std::fs::DirBuilder;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+use crate::{error::ApiError, AppState, IndexerError};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const MAX_SOURCE_BYTES: usize = 1024 * 1024;
+pub const MAX_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+const MAX_DIAGNOSTIC_CHARS: usize = 2000;
+
+/// Settings for the optional `POST /format` stage.
+#[derive(Clone, Debug)]
+pub struct FormatConfig {
+    pub enabled: bool,
+    pub timeout: Duration,
+}
+
+impl FormatConfig {
+    /// Reads `INDEXER_FORMAT_ENABLED` (default off) and
+    /// `INDEXER_FORMAT_TIMEOUT_MS` (default 10000).
+    pub fn from_env() -> Result<Self, IndexerError> {
+        let enabled = match std::env::var("INDEXER_FORMAT_ENABLED") {
+            Ok(raw) => parse_bool(&raw).ok_or_else(|| {
+                IndexerError::Config(format!(
+                    "INDEXER_FORMAT_ENABLED must be a boolean, got {raw:?}"
+                ))
+            })?,
+            Err(_) => false,
+        };
+        let timeout = match std::env::var("INDEXER_FORMAT_TIMEOUT_MS") {
+            Ok(raw) => raw
+                .trim()
+                .parse::<u64>()
+                .map(Duration::from_millis)
+                .map_err(|_| {
+                    IndexerError::Config(format!(
+                        "INDEXER_FORMAT_TIMEOUT_MS must be an integer, got {raw:?}"
+                    ))
+                })?,
+            Err(_) => DEFAULT_TIMEOUT,
+        };
+        Ok(Self { enabled, timeout })
+    }
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" | "" => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FormatRequest {
+    pub language: String,
+    pub source: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FormatResponse {
+    pub formatted: String,
+    pub formatter: &'static str,
+    pub changed: bool,
+}
+
+/// External formatter invocation; the source is piped through stdin.
+#[derive(Debug, PartialEq, Eq)]
+struct Formatter {
+    name: &'static str,
+    program: &'static str,
+    args: Vec<String>,
+    /// Flag naming an explicit config file. An empty one is passed so the
+    /// tool never searches the working directory or its parents for config.
+    config_flag: Option<&'static str>,
+}
+
+/// Languages accepted by `POST /format`.
+pub const LANGUAGES: [&str; 6] = ["rust", "python", "typescript", "tsx", "javascript", "json"];
+
+/// Name of the tool that formats `language`, if any.
+pub fn formatter_name(language: &str) -> Option<&'static str> {
+    formatter_for(language).map(|formatter| formatter.name)
+}
+
+fn formatter_for(language: &str) -> Option<Formatter> {
+    // Prettier only uses the file name to pick a parser; it never reads it.
+    let prettier = |extension: &str| Formatter {
+        name: "prettier",
+        program: "prettier",
+        args: vec![
+            "--no-config".into(),
+            "--no-editorconfig".into(),
+            "--stdin-filepath".into(),
+            format!("stdin.{extension}"),
+        ],
+        config_flag: None,
+    };
+    match language {
+        "rust" => Some(Formatter {
+            name: "rustfmt",
+            program: "rustfmt",
+            args: vec!["--edition".into(), "2021".into()],
+            config_flag: Some("--config-path"),
+        }),
+        "python" => Some(Formatter {
+            name: "black",
+            program: "black",
+            args: vec!["--quiet".into(), "-".into()],
+            config_flag: Some("--config"),
+        }),
+        "typescript" => Some(prettier("ts")),
+        "tsx" => Some(prettier("tsx")),
+        "javascript" => Some(prettier("js")),
+        "json" => Some(prettier("json")),
+        _ => None,
+    }
+}
+
+pub async fn format(
+    State(state): State<AppState>,
+    Json(req): Json<FormatRequest>,
+) -> Result<Json<FormatResponse>, ApiError> {
+    if !state.format.enabled {
+        return Err(ApiError::Unavailable(
+            "formatting is disabled; set INDEXER_FORMAT_ENABLED=true".into(),
+        ));
+    }
+    if req.source.len() > MAX_SOURCE_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "source exceeds {MAX_SOURCE_BYTES} bytes"
+        )));
+    }
+    let formatter = formatter_for(&req.language).ok_or_else(|| {
+        ApiError::BadRequest(format!("no formatter for language {:?}", req.language))
+    })?;
+
+    let formatted = run_formatter(&formatter, &req.source, state.format.timeout).await?;
+    Ok(Json(FormatResponse {
+        changed: formatted != req.source,
+        formatted,
+        formatter: formatter.name,
+    }))
+}
+
+/// Private, initially empty working directory for one formatter run, removed
+/// on drop. Running in a shared directory such as `/tmp` would let other local
+/// users plant config files (and prettier plugins) that the formatter loads.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn create(base: &Path) -> io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.subsec_nanos());
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        loop {
+            let attempt = NEXT.fetch_add(1, Ordering::Relaxed);
+            let path = base.join(format!(
+                "ossaat-format-{}-{nanos}-{attempt}",
+                std::process::id()
+            ));
+            // `create` fails on an existing path, so a directory pre-created by
+            // someone else is never reused.
+            match builder.create(&path) {
+                Ok(()) => return Ok(Self(path)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempt % 16 != 15 => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Runs `formatter` in a scratch directory with a cleared environment, killing
+/// it if it outlives `timeout` or produces more output than we accept.
+async fn run_formatter(
+    formatter: &Formatter,
+    source: &str,
+    timeout: Duration,
+) -> Result<String, ApiError> {
+    run_formatter_in(&std::env::temp_dir(), formatter, source, timeout).await
+}
+
+/// Like [`run_formatter`], but creates the scratch directory under `base`.
+async fn run_formatter_in(
+    base: &Path,
+    formatter: &Formatter,
+    source: &str,
+    timeout: Duration,
+) -> Result<String, ApiError> {
+    let scratch = ScratchDir::create(base).map_err(|err| {
+        ApiError::Internal(format!(
+            "failed to create formatter working directory: {err}"
+        ))
+    })?;
+    let mut command = Command::new(formatter.program);
+    if let Some(flag) = formatter.config_flag {
+        let config = scratch.0.join("empty.toml");
+        std::fs::write(&config, "").map_err(|err| {
+            ApiError::Internal(format!("failed to write formatter config: {err}"))
+        })?;
+        command.arg(flag).arg(config);
+    }
+    command
+        .args(&formatter.args)
+        .current_dir(&scratch.0)
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = command.spawn().map_err(|err| {
+        ApiError::Unavailable(format!("failed to start {}: {err}", formatter.program))
+    })?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let mut stdout = child
+        .stdout
+        .take()
+        .expect("stdout is piped")
+        .take(MAX_OUTPUT_BYTES as u64 + 1);
+    let mut stderr = child
+        .stderr
+        .take()
+        .expect("stderr is piped")
+        .take(MAX_OUTPUT_BYTES as u64);
+    let input = source.as_bytes().to_vec();
+
+    let io = async {
+        let write = async {
+            // The formatter may exit early on a syntax error; its stderr is what matters then.
+            let _ = stdin.write_all(&input).await;
+            drop(stdin);
+        };
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let read_out = stdout.read_to_end(&mut out);
+        let read_err = stderr.read_to_end(&mut err);
+        let ((), out_res, err_res) = tokio::join!(write, read_out, read_err);
+        out_res.and(err_res)?;
+        if out.len() > MAX_OUTPUT_BYTES {
+            // Don't wait on a child that may be blocked writing; kill_on_drop reaps it.
+            return Ok(None);
+        }
+        let status = child.wait().await?;
+        Ok::<_, std::io::Error>(Some((status, out, err)))
+    };
+
+    let (status, out, err) = match tokio::time::timeout(timeout, io).await {
+        Ok(Ok(Some(output))) => output,
+        Ok(Ok(None)) => {
+            return Err(ApiError::Internal(format!(
+                "{} produced more than {MAX_OUTPUT_BYTES} bytes",
+                formatter.program
+            )))
+        }
+        Ok(Err(err)) => {
+            return Err(ApiError::Internal(format!(
+                "{} I/O failed: {err}",
+                formatter.program
+            )))
+        }
+        Err(_) => {
+            return Err(ApiError::Timeout(format!(
+                "{} did not finish within {}ms",
+                formatter.program,
+                timeout.as_millis()
+            )))
+        }
+    };
+
+    if !status.success() {
+        let diagnostic: String = String::from_utf8_lossy(&err)
+            .trim()
+            .chars()
+            .take(MAX_DIAGNOSTIC_CHARS)
+            .collect();
+        return Err(ApiError::Unprocessable(format!(
+            "{} rejected the source: {diagnostic}",
+            formatter.program
+        )));
+    }
+    String::from_utf8(out)
+        .map_err(|_| ApiError::Internal(format!("{} emitted invalid UTF-8", formatter.program)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_map_to_formatters() {
+        for language in LANGUAGES {
+            assert!(formatter_name(language).is_some(), "{language}");
+        }
+        assert_eq!(formatter_for("rust").unwrap().name, "rustfmt");
+        assert_eq!(formatter_for("python").unwrap().name, "black");
+        assert_eq!(
+            formatter_for("tsx").unwrap().args,
+            &[
+                "--no-config",
+                "--no-editorconfig",
+                "--stdin-filepath",
+                "stdin.tsx"
+            ]
+        );
+        assert!(formatter_for("cobol").is_none());
+    }
+
+    #[test]
+    fn parse_bool_accepts_common_spellings() {
+        assert_eq!(parse_bool("TRUE"), Some(true));
+        assert_eq!(parse_bool("off"), Some(false));
+        assert_eq!(parse_bool("maybe"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn formatter_output_is_returned() {
+        let cat = Formatter {
+            name: "cat",
+            program: "cat",
+            args: Vec::new(),
+            config_flag: None,
+        };
+        let out = run_formatter(&cat, "fn main() {}\n", DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(out, "fn main() {}\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn formatter_is_killed_after_timeout() {
+        let sleep = Formatter {
+            name: "sleep",
+            program: "sleep",
+            args: vec!["5".into()],
+            config_flag: None,
+        };
+        let err = run_formatter(&sleep, "", Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::Timeout(_)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failing_formatter_reports_unprocessable() {
+        let fail = Formatter {
+            name: "false",
+            program: "false",
+            args: Vec::new(),
+            config_flag: None,
+        };
+        let err = run_formatter(&fail, "x", DEFAULT_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::Unprocessable(_)));
+    }
+
+    #[tokio::test]
+    async fn disabled_stage_is_unavailable() {
+        let result = format(
+            State(AppState::default()),
+            Json(FormatRequest {
+                language: "rust".into(),
+                source: "fn main(){}".into(),
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::Unavailable(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn formatter_runs_in_a_private_empty_directory() {
+        let base = std::env::temp_dir().join(format!("indexer-format-{}", std::process::id()));
+        std::fs::create_dir_all(&base).unwrap();
+        // What another local user could plant in a shared temp directory.
+        std::fs::write(base.join("rustfmt.toml"), "hard_tabs = true\n").unwrap();
+        std::fs::write(base.join(".prettierignore"), "*\n").unwrap();
+
+        let probe = Formatter {
+            name: "sh",
+            program: "sh",
+            args: vec!["-c".into(), "ls -ld . | cut -c1-10 && ls -A".into()],
+            config_flag: None,
+        };
+        let out = run_formatter_in(&base, &probe, "", DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(out, "drwx------\n");
+
+        // rustfmt would otherwise pick up ../rustfmt.toml and indent with tabs.
+        let rustfmt = formatter_for("rust").unwrap();
+        match run_formatter_in(&base, &rustfmt, "fn main() { let x = 1; }", DEFAULT_TIMEOUT).await {
+            Ok(out) => assert_eq!(out, "fn main() {\n    let x = 1;\n}\n"),
+            Err(ApiError::Unavailable(_)) => {} // rustfmt not installed
+            Err(err) => panic!("{err}"),
+        }
+
+        let leftovers: Vec<_> = std::fs::read_dir(&base)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with("ossaat-format-")
+            })
+            .collect();
+        assert!(leftovers.is_empty(), "scratch directories were not removed");
+        std::fs::remove_dir_all(base).unwrap();
+    }
+}
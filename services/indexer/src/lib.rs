@@ -0,0 +1,159 @@
+mod access;
+mod admin;
+mod capabilities;
+mod diagnostics;
+mod error;
+mod format;
+mod logging;
+mod metrics;
+mod middleware;
+mod positions;
+mod shutdown;
+
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::diagnostics::Diagnostics;
+use crate::logging::{LogConfig, LogLevel};
+use crate::metrics::Metrics;
+use crate::shutdown::ShutdownCoordinator;
+
+pub use crate::access::AllowList;
+pub use crate::format::FormatConfig;
+
+/// Shared state handed to every handler.
+#[derive(Clone, Debug, Default)]
+pub struct AppState {
+    metrics: Arc<Metrics>,
+    diagnostics: Arc<Diagnostics>,
+    format: Arc<FormatConfig>,
+    allow_list: Arc<AllowList>,
+    log_level: Arc<LogLevel>,
+    admin_token: Option<Arc<str>>,
+}
+
+impl AppState {
+    pub fn new(format: FormatConfig, allow_list: AllowList) -> Self {
+        Self {
+            format: Arc::new(format),
+            allow_list: Arc::new(allow_list),
+            ..Self::default()
+        }
+    }
+
+    /// Enables the `/admin/*` endpoints for callers presenting `token`.
+    pub fn with_admin_token(mut self, token: impl Into<Arc<str>>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HealthResponse {
+    status: &'static str,
+}
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("invalid configuration: {0}")]
+    Config(String),
+    #[error("bind error: {0}")]
+    Bind(#[source] std::io::Error),
+    #[error("signal handling error: {0}")]
+    Signal(#[source] std::io::Error),
+    #[error("server error: {0}")]
+    Server(#[source] std::io::Error),
+}
+
+async fn healthcheck() -> Json<HealthResponse> {
+    Json(HealthResponse { status: "ok" })
+}
+
+/// Builds the full HTTP API with its middleware stack.
+pub fn app(state: AppState) -> Router {
+    let admin = Router::new()
+        .route("/admin/diagnostics", get(diagnostics::diagnostics))
+        .route(
+            "/admin/log-level",
+            get(admin::get_log_level).put(admin::set_log_level),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            admin::require_admin,
+        ));
+    let router = Router::new()
+        .route("/healthz", get(healthcheck))
+        .route("/metrics", get(metrics::metrics))
+        .route("/capabilities", get(capabilities::capabilities))
+        .route("/format", post(format::format))
+        .route("/positions", post(positions::positions))
+        .merge(admin);
+    middleware::apply(router, &state).with_state(state)
+}
+
+/// Reads the listen address from `INDEXER_HTTP_ADDR`, defaulting to loopback
+/// so the API is never exposed by accident.
+fn listen_addr() -> Result<SocketAddr, IndexerError> {
+    match std::env::var("INDEXER_HTTP_ADDR") {
+        Ok(raw) => raw.trim().parse().map_err(|_| {
+            IndexerError::Config(format!(
+                "INDEXER_HTTP_ADDR must be a socket address like 127.0.0.1:7070, got {raw:?}"
+            ))
+        }),
+        Err(_) => Ok(SocketAddr::from(([127, 0, 0, 1], 7070))),
+    }
+}
+
+pub async fn run() -> Result<(), IndexerError> {
+    let log_level = logging::init(&LogConfig::from_env()?)?;
+
+    let coordinator = ShutdownCoordinator::from_env()?;
+
+    let mut state = AppState {
+        log_level: Arc::new(log_level),
+        ..AppState::new(FormatConfig::from_env()?, AllowList::from_env()?)
+    };
+    if let Some(token) = admin::token_from_env() {
+        state = state.with_admin_token(token);
+    }
+
+    let addr = listen_addr()?;
+    if !addr.ip().is_loopback() && state.allow_list.is_empty() {
+        warn!(%addr, "listening on a non-loopback address without INDEXER_HTTP_ALLOW_CIDRS");
+    }
+    let app = app(state);
+    let listener = TcpListener::bind(addr).await.map_err(IndexerError::Bind)?;
+    let bound_addr = listener.local_addr().map_err(IndexerError::Bind)?;
+    info!(%bound_addr, "starting indexer");
+
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(coordinator.stop_accepting())
+    .into_future();
+    coordinator.run(server).await?;
+
+    info!("indexer stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn healthcheck_returns_ok() {
+        let Json(resp) = healthcheck().await;
+        assert_eq!(resp.status, "ok");
+    }
+}
@@ -0,0 +1,243 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing::Subscriber;
+use tracing_subscriber::{
+    fmt::{self, writer::BoxMakeWriter},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+use crate::IndexerError;
+
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Where and how tracing output is written.
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    pub file: Option<PathBuf>,
+    pub max_bytes: u64,
+    pub max_files: usize,
+}
+
+impl LogConfig {
+    /// Reads `INDEXER_LOG_FORMAT` (`text` or `json`, default `text`),
+    /// `INDEXER_LOG_FILE` (default stdout), `INDEXER_LOG_MAX_BYTES` (default
+    /// 10 MiB) and `INDEXER_LOG_MAX_FILES` (rotated files kept, default 5).
+    pub fn from_env() -> Result<Self, IndexerError> {
+        let format = match std::env::var("INDEXER_LOG_FORMAT") {
+            Ok(raw) => match raw.trim().to_ascii_lowercase().as_str() {
+                "" | "text" => LogFormat::Text,
+                "json" => LogFormat::Json,
+                _ => {
+                    return Err(IndexerError::Config(format!(
+                        "INDEXER_LOG_FORMAT must be text or json, got {raw:?}"
+                    )))
+                }
+            },
+            Err(_) => LogFormat::Text,
+        };
+        let file = std::env::var_os("INDEXER_LOG_FILE")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        Ok(Self {
+            format,
+            file,
+            max_bytes: env_number("INDEXER_LOG_MAX_BYTES", DEFAULT_MAX_BYTES)?,
+            max_files: env_number("INDEXER_LOG_MAX_FILES", DEFAULT_MAX_FILES)?,
+        })
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str, default: T) -> Result<T, IndexerError> {
+    match std::env::var(name) {
+        Ok(raw) => raw
+            .trim()
+            .parse()
+            .map_err(|_| IndexerError::Config(format!("{name} must be an integer, got {raw:?}"))),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Handle for swapping the `RUST_LOG`-style filter at runtime. The default
+/// value has no subscriber behind it and rejects changes.
+#[derive(Debug, Default)]
+pub struct LogLevel {
+    handle: Option<reload::Handle<EnvFilter, Registry>>,
+}
+
+impl LogLevel {
+    #[cfg(test)]
+    pub fn from_handle(handle: reload::Handle<EnvFilter, Registry>) -> Self {
+        Self {
+            handle: Some(handle),
+        }
+    }
+
+    pub fn current(&self) -> Option<String> {
+        self.handle.as_ref()?.with_current(ToString::to_string).ok()
+    }
+
+    /// Parses `directives` and installs them as the new filter.
+    pub fn set(&self, directives: &str) -> Result<String, String> {
+        let handle = self
+            .handle
+            .as_ref()
+            .ok_or("logging was not initialised with a reloadable filter")?;
+        let filter = EnvFilter::try_new(directives).map_err(|err| err.to_string())?;
+        let rendered = filter.to_string();
+        handle.reload(filter).map_err(|err| err.to_string())?;
+        Ok(rendered)
+    }
+}
+
+/// Installs the global subscriber. `RUST_LOG` sets the initial filter; the
+/// returned handle can replace it later.
+pub fn init(config: &LogConfig) -> Result<LogLevel, IndexerError> {
+    let (filter, handle) = reload::Layer::new(EnvFilter::from_default_env());
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(config)?)
+        .init();
+    Ok(LogLevel {
+        handle: Some(handle),
+    })
+}
+
+fn fmt_layer<S>(config: &LogConfig) -> Result<Box<dyn Layer<S> + Send + Sync>, IndexerError>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let writer = match &config.file {
+        Some(path) => {
+            let file =
+                RotatingFile::open(path, config.max_bytes, config.max_files).map_err(|err| {
+                    IndexerError::Config(format!("INDEXER_LOG_FILE {}: {err}", path.display()))
+                })?;
+            BoxMakeWriter::new(Mutex::new(file))
+        }
+        None => BoxMakeWriter::new(io::stdout),
+    };
+    let layer = fmt::layer().with_writer(writer);
+    // The request span carries the request id, so every event logged while
+    // handling a request is tagged with it.
+    Ok(match config.format {
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+        // Files have no collector adding timestamps or stripping colours.
+        LogFormat::Text if config.file.is_some() => layer.with_ansi(false).boxed(),
+        LogFormat::Text => layer.without_time().boxed(),
+    })
+}
+
+/// Append-only log file that is rotated to `<path>.1`, `<path>.2`, ... once it
+/// exceeds `max_bytes`, keeping at most `max_files` old files.
+#[derive(Debug)]
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_owned(),
+            file,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated(1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_shifts_files_and_drops_the_oldest() {
+        let dir = std::env::temp_dir().join(format!("indexer-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("indexer.log");
+
+        let mut log = RotatingFile::open(&path, 10, 2).unwrap();
+        for line in [
+            "first-line\n",
+            "second-line\n",
+            "third-line\n",
+            "fourth-line\n",
+        ] {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "fourth-line\n");
+        assert_eq!(read(log.rotated(1)), "third-line\n");
+        assert_eq!(read(log.rotated(2)), "second-line\n");
+        assert!(!log.rotated(3).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+use axum::{extract::State, http::header, response::IntoResponse};
+use prometheus::{Encoder, IntCounter, Registry, TextEncoder};
+
+use crate::AppState;
+
+/// Prometheus registry owned by the indexer plus the collectors registered in it.
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    pub http_panics: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let http_panics = IntCounter::new(
+            "indexer_http_panics_total",
+            "Number of HTTP handler panics converted into 500 responses",
+        )
+        .expect("valid counter definition");
+        registry
+            .register(Box::new(http_panics.clone()))
+            .expect("collector registered once");
+
+        Self {
+            registry,
+            http_panics,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding into a Vec cannot fail");
+        String::from_utf8(buffer).expect("prometheus text format is UTF-8")
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        state.metrics.render(),
+    )
+}
@@ -0,0 +1,205 @@
+use std::any::Any;
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde::Serialize;
+use tower_http::{
+    catch_panic::CatchPanicLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+};
+use tracing::{error, info_span, Instrument};
+
+use crate::{access, error::ErrorMessage, AppState};
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+#[derive(Debug, Serialize)]
+struct PanicResponse {
+    error: &'static str,
+    request_id: Option<String>,
+}
+
+/// Wraps `router` with the cross-cutting HTTP layers: request ids are assigned
+/// (or propagated from `x-request-id`), peers outside the allowlist are
+/// rejected, handler panics become 500 responses instead of tearing down the
+/// connection task, and every 5xx response is kept for `/admin/diagnostics`.
+pub fn apply(router: Router<AppState>, state: &AppState) -> Router<AppState> {
+    let panic_state = state.clone();
+
+    router
+        .layer(CatchPanicLayer::custom(move |payload| {
+            panic_response(&panic_state, payload)
+        }))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            record_server_errors,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            access::enforce_allow_list,
+        ))
+        .layer(middleware::from_fn(scope_request_id))
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+/// Returns the id of the request currently being handled, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+async fn scope_request_id(req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_owned();
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+    REQUEST_ID
+        .scope(request_id, next.run(req))
+        .instrument(span)
+        .await
+}
+
+async fn record_server_errors(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let status = response.status();
+    if status.is_server_error() {
+        let message = response.extensions().get::<ErrorMessage>().map_or_else(
+            || status.to_string(),
+            |ErrorMessage(message)| format!("{status}: {message}"),
+        );
+        state
+            .diagnostics
+            .record_error(current_request_id(), message);
+    }
+    response
+}
+
+fn panic_response(state: &AppState, payload: Box<dyn Any + Send + 'static>) -> Response {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_owned());
+    let request_id = current_request_id();
+
+    state.metrics.http_panics.inc();
+    error!(request_id = request_id.as_deref().unwrap_or("-"), panic = %message, "handler panicked");
+
+    let mut response = (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(PanicResponse {
+            error: "internal server error",
+            request_id,
+        }),
+    )
+        .into_response();
+    response
+        .extensions_mut()
+        .insert(ErrorMessage(format!("handler panicked: {message}")));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get};
+    use tower::ServiceExt;
+
+    async fn boom() -> &'static str {
+        panic!("tree-sitter edge case")
+    }
+
+    async fn slow() -> Result<(), crate::error::ApiError> {
+        Err(crate::error::ApiError::Timeout("rustfmt timed out".into()))
+    }
+
+    async fn bad() -> Result<(), crate::error::ApiError> {
+        Err(crate::error::ApiError::BadRequest("no formatter".into()))
+    }
+
+    fn panicking_app(state: &AppState) -> Router {
+        let router = Router::new()
+            .route("/boom", get(boom))
+            .route("/slow", get(slow))
+            .route("/bad", get(bad));
+        apply(router, state).with_state(state.clone())
+    }
+
+    #[tokio::test]
+    async fn panic_becomes_500_with_request_id() {
+        let state = AppState::default();
+        let response = panicking_app(&state)
+            .oneshot(
+                Request::get("/boom")
+                    .header("x-request-id", "req-42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers()["x-request-id"], "req-42");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["request_id"], "req-42");
+        assert_eq!(state.metrics.http_panics.get(), 1);
+        let errors = state.diagnostics.recent_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].request_id.as_deref(), Some("req-42"));
+        assert_eq!(
+            errors[0].message,
+            "500 Internal Server Error: handler panicked: tree-sitter edge case"
+        );
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_recorded_but_client_errors_are_not() {
+        let state = AppState::default();
+        for path in ["/bad", "/slow"] {
+            panicking_app(&state)
+                .oneshot(
+                    Request::get(path)
+                        .header("x-request-id", "req-7")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let errors = state.diagnostics.recent_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].request_id.as_deref(), Some("req-7"));
+        assert_eq!(errors[0].message, "504 Gateway Timeout: rustfmt timed out");
+    }
+
+    #[tokio::test]
+    async fn request_id_is_generated_when_missing() {
+        let state = AppState::default();
+        let response = panicking_app(&state)
+            .oneshot(Request::get("/boom").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let generated = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(!generated.is_empty());
+    }
+}
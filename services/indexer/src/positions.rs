@@ -0,0 +1,264 @@
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+
+const MAX_SOURCE_BYTES: usize = 1024 * 1024;
+const MAX_POSITIONS: usize = 10_000;
+
+/// A line/column pair. `character` counts UTF-8 bytes or UTF-16 code units
+/// depending on where it appears, matching LSP's `positionEncoding`.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct LineCol {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// A tree-sitter `Point`: zero-based row and byte column.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Point {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// One position to convert, in whichever encoding the client has.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionInput {
+    Byte(usize),
+    Utf8(LineCol),
+    Utf16(LineCol),
+    Point(Point),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PositionsRequest {
+    pub source: String,
+    pub positions: Vec<PositionInput>,
+}
+
+/// The same position in every supported encoding.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Position {
+    pub byte: usize,
+    pub utf8: LineCol,
+    pub utf16: LineCol,
+    pub point: Point,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PositionsResponse {
+    pub positions: Vec<Position>,
+}
+
+/// Byte offsets of line starts. Lines end at `\n` only, as in tree-sitter, so
+/// a `\r` before it counts as the last character of its line.
+struct LineIndex<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { source, starts }
+    }
+
+    /// Text of `line` without its trailing newline.
+    fn line(&self, line: usize) -> Option<(usize, &'a str)> {
+        let start = *self.starts.get(line)?;
+        let end = self
+            .starts
+            .get(line + 1)
+            .map_or(self.source.len(), |next| next - 1);
+        Some((start, &self.source[start..end]))
+    }
+
+    fn resolve(&self, input: PositionInput) -> Result<Position, String> {
+        let byte = match input {
+            PositionInput::Byte(byte) => byte,
+            PositionInput::Utf8(pos) => self.utf8_to_byte(pos.line, pos.character)?,
+            PositionInput::Point(point) => self.utf8_to_byte(point.row, point.column)?,
+            PositionInput::Utf16(pos) => self.utf16_to_byte(pos)?,
+        };
+        self.at_byte(byte)
+    }
+
+    fn at_byte(&self, byte: usize) -> Result<Position, String> {
+        if byte > self.source.len() {
+            return Err(format!(
+                "byte offset {byte} is past the end of the document ({} bytes)",
+                self.source.len()
+            ));
+        }
+        if !self.source.is_char_boundary(byte) {
+            return Err(format!(
+                "byte offset {byte} is inside a multi-byte character"
+            ));
+        }
+        let line = self.starts.partition_point(|&start| start <= byte) - 1;
+        let start = self.starts[line];
+        let column = byte - start;
+        let utf16 = self.source[start..byte].encode_utf16().count();
+        Ok(Position {
+            byte,
+            utf8: LineCol {
+                line,
+                character: column,
+            },
+            utf16: LineCol {
+                line,
+                character: utf16,
+            },
+            point: Point { row: line, column },
+        })
+    }
+
+    fn utf8_to_byte(&self, line: usize, column: usize) -> Result<usize, String> {
+        let (start, text) = self
+            .line(line)
+            .ok_or_else(|| format!("line {line} is past the end of the document"))?;
+        if column > text.len() {
+            return Err(format!(
+                "column {column} is past the end of line {line} ({} bytes)",
+                text.len()
+            ));
+        }
+        Ok(start + column)
+    }
+
+    fn utf16_to_byte(&self, pos: LineCol) -> Result<usize, String> {
+        let (start, text) = self
+            .line(pos.line)
+            .ok_or_else(|| format!("line {} is past the end of the document", pos.line))?;
+        let mut units = 0;
+        for (offset, ch) in text.char_indices() {
+            if units == pos.character {
+                return Ok(start + offset);
+            }
+            units += ch.len_utf16();
+            if units > pos.character {
+                return Err(format!(
+                    "UTF-16 column {} on line {} is inside a surrogate pair",
+                    pos.character, pos.line
+                ));
+            }
+        }
+        if units == pos.character {
+            Ok(start + text.len())
+        } else {
+            Err(format!(
+                "UTF-16 column {} is past the end of line {} ({units} code units)",
+                pos.character, pos.line
+            ))
+        }
+    }
+}
+
+/// Converts positions between byte offsets, UTF-8 and UTF-16 line/column
+/// pairs and tree-sitter points so clients using different encodings agree
+/// on where an edit lands.
+pub async fn positions(
+    Json(req): Json<PositionsRequest>,
+) -> Result<Json<PositionsResponse>, ApiError> {
+    if req.source.len() > MAX_SOURCE_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "source exceeds {MAX_SOURCE_BYTES} bytes"
+        )));
+    }
+    if req.positions.len() > MAX_POSITIONS {
+        return Err(ApiError::BadRequest(format!(
+            "at most {MAX_POSITIONS} positions per request"
+        )));
+    }
+
+    let index = LineIndex::new(&req.source);
+    let positions = req
+        .positions
+        .iter()
+        .enumerate()
+        .map(|(i, &input)| {
+            index
+                .resolve(input)
+                .map_err(|err| ApiError::BadRequest(format!("positions[{i}]: {err}")))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Json(PositionsResponse { positions }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "é" is two UTF-8 bytes and one UTF-16 unit; "😀" is four and two.
+    const SOURCE: &str = "let é = 1;\r\nfoo(\"😀\", x)\n";
+
+    fn at(line: usize, utf8: usize, utf16: usize) -> (LineCol, LineCol) {
+        (
+            LineCol {
+                line,
+                character: utf8,
+            },
+            LineCol {
+                line,
+                character: utf16,
+            },
+        )
+    }
+
+    #[test]
+    fn every_encoding_round_trips() {
+        let index = LineIndex::new(SOURCE);
+        // The `x` after the emoji: byte 24, UTF-8 column 11, UTF-16 column 9.
+        let (utf8, utf16) = at(1, 11, 9);
+        let expected = index.at_byte(24).unwrap();
+        assert_eq!(expected.utf8, utf8);
+        assert_eq!(expected.utf16, utf16);
+        assert_eq!(expected.point, Point { row: 1, column: 11 });
+
+        for input in [
+            PositionInput::Utf8(utf8),
+            PositionInput::Utf16(utf16),
+            PositionInput::Point(Point { row: 1, column: 11 }),
+        ] {
+            assert_eq!(index.resolve(input).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn line_ends_and_document_end_are_valid() {
+        let index = LineIndex::new(SOURCE);
+        // Column 11 on line 0 sits between `\r` and `\n`.
+        let (utf8, utf16) = at(0, 11, 10);
+        assert_eq!(
+            index.resolve(PositionInput::Utf8(utf8)).unwrap().utf16,
+            utf16
+        );
+
+        let end = index.at_byte(SOURCE.len()).unwrap();
+        assert_eq!(end.point, Point { row: 2, column: 0 });
+    }
+
+    #[test]
+    fn positions_inside_characters_are_rejected() {
+        let index = LineIndex::new(SOURCE);
+        assert!(index.at_byte(5).unwrap_err().contains("multi-byte"));
+        let (_, utf16) = at(1, 0, 6);
+        let err = index.resolve(PositionInput::Utf16(utf16)).unwrap_err();
+        assert!(err.contains("surrogate pair"), "{err}");
+    }
+
+    #[test]
+    fn out_of_range_positions_are_rejected() {
+        let index = LineIndex::new(SOURCE);
+        assert!(index.at_byte(SOURCE.len() + 1).is_err());
+        assert!(index
+            .resolve(PositionInput::Point(Point { row: 3, column: 0 }))
+            .is_err());
+        let (utf8, utf16) = at(1, 15, 13);
+        assert!(index.resolve(PositionInput::Utf8(utf8)).is_err());
+        assert!(index.resolve(PositionInput::Utf16(utf16)).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::IndexerError;
+
+const DEFAULT_GRACE: Duration = Duration::from_secs(30);
+
+/// Owns the shutdown sequence: wait for a termination signal, stop accepting
+/// new connections, then give in-flight requests a bounded window to finish.
+#[derive(Debug)]
+pub struct ShutdownCoordinator {
+    grace: Duration,
+    triggered: watch::Sender<bool>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new(grace: Duration) -> Self {
+        let (triggered, _) = watch::channel(false);
+        Self { grace, triggered }
+    }
+
+    /// Reads the drain window from `INDEXER_SHUTDOWN_GRACE_SECS` (default 30).
+    pub fn from_env() -> Result<Self, IndexerError> {
+        let grace = match std::env::var("INDEXER_SHUTDOWN_GRACE_SECS") {
+            Ok(raw) => raw
+                .trim()
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|_| {
+                    IndexerError::Config(format!(
+                        "INDEXER_SHUTDOWN_GRACE_SECS must be an integer, got {raw:?}"
+                    ))
+                })?,
+            Err(_) => DEFAULT_GRACE,
+        };
+        Ok(Self::new(grace))
+    }
+
+    /// Resolves once shutdown has started; hand this to the server's graceful
+    /// shutdown hook so it stops accepting new connections.
+    pub fn stop_accepting(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut rx = self.triggered.subscribe();
+        async move {
+            let _ = rx.wait_for(|triggered| *triggered).await;
+        }
+    }
+
+    /// Drives `server` until a termination signal arrives, then drains it.
+    pub async fn run<S>(&self, server: S) -> Result<(), IndexerError>
+    where
+        S: Future<Output = std::io::Result<()>>,
+    {
+        self.run_until(server, async {
+            if let Err(err) = shutdown_signal().await {
+                error!(%err, "shutdown signal error");
+            }
+        })
+        .await
+    }
+
+    async fn run_until<S, T>(&self, server: S, trigger: T) -> Result<(), IndexerError>
+    where
+        S: Future<Output = std::io::Result<()>>,
+        T: Future<Output = ()>,
+    {
+        tokio::pin!(server);
+        tokio::select! {
+            res = &mut server => return res.map_err(IndexerError::Server),
+            _ = trigger => {}
+        }
+
+        info!(
+            grace_secs = self.grace.as_secs(),
+            "shutdown requested, draining in-flight requests"
+        );
+        self.triggered.send_replace(true);
+
+        match tokio::time::timeout(self.grace, server).await {
+            Ok(res) => res.map_err(IndexerError::Server),
+            Err(_) => {
+                warn!("drain window elapsed, abandoning in-flight requests");
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn shutdown_signal() -> Result<(), IndexerError> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate()).map_err(IndexerError::Signal)?;
+        tokio::select! {
+            res = tokio::signal::ctrl_c() => res.map_err(IndexerError::Signal)?,
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c()
+            .await
+            .map_err(IndexerError::Signal)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn server_exit_without_signal_is_returned() {
+        let coordinator = ShutdownCoordinator::new(Duration::from_secs(1));
+        let result = coordinator
+            .run_until(async { Ok(()) }, std::future::pending())
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn drain_waits_for_server_to_stop_accepting() {
+        let coordinator = ShutdownCoordinator::new(Duration::from_secs(5));
+        let stopped = coordinator.stop_accepting();
+        let server = async move {
+            stopped.await;
+            Ok(())
+        };
+        let result = coordinator.run_until(server, async {}).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn drain_gives_up_after_grace_period() {
+        let coordinator = ShutdownCoordinator::new(Duration::from_millis(10));
+        let result = coordinator
+            .run_until(std::future::pending(), async {})
+            .await;
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,258 @@
+//! Drives the full router over real HTTP on an ephemeral port.
+//!
+//! Response bodies that are stable across runs are compared against JSON
+//! files in `tests/golden/`. Run with `UPDATE_GOLDEN=1` to rewrite them after
+//! an intentional API change.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use axum::body::Bytes;
+use axum::http::{header, Method, Request, StatusCode};
+use http_body_util::{BodyExt, Full};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper_util::rt::TokioExecutor;
+use ossaat_indexer::{AllowList, AppState, FormatConfig};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+struct TestServer {
+    addr: SocketAddr,
+    client: Client<HttpConnector, Full<Bytes>>,
+}
+
+impl TestServer {
+    async fn start(state: AppState) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = ossaat_indexer::app(state);
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+        let client = Client::builder(TokioExecutor::new()).build_http();
+        Self { addr, client }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        bearer: Option<&str>,
+    ) -> Response {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(format!("http://{}{path}", self.addr));
+        if let Some(token) = bearer {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let body = match body {
+            Some(json) => {
+                builder = builder.header(header::CONTENT_TYPE, "application/json");
+                Full::from(serde_json::to_vec(&json).unwrap())
+            }
+            None => Full::default(),
+        };
+        let response = self
+            .client
+            .request(builder.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_owned());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        Response {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    async fn get(&self, path: &str) -> Response {
+        self.request(Method::GET, path, None, None).await
+    }
+
+    async fn get_as(&self, path: &str, token: &str) -> Response {
+        self.request(Method::GET, path, None, Some(token)).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Response {
+        self.request(Method::POST, path, Some(body), None).await
+    }
+}
+
+struct Response {
+    status: StatusCode,
+    content_type: Option<String>,
+    body: Bytes,
+}
+
+impl Response {
+    fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+
+    fn text(&self) -> &str {
+        std::str::from_utf8(&self.body).unwrap()
+    }
+}
+
+fn assert_golden(name: &str, actual: &Value) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    let rendered = serde_json::to_string_pretty(actual).unwrap() + "\n";
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, rendered).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("reading {}: {err}", path.display()));
+    assert_eq!(
+        rendered,
+        expected,
+        "response differs from {}",
+        path.display()
+    );
+}
+
+#[tokio::test]
+async fn healthz_reports_ok() {
+    let server = TestServer::start(AppState::default()).await;
+    let response = server.get("/healthz").await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.json(), json!({ "status": "ok" }));
+}
+
+#[tokio::test]
+async fn capabilities_match_golden() {
+    let server = TestServer::start(AppState::default()).await;
+    let response = server.get("/capabilities").await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let mut body = response.json();
+    body["version"] = json!("[version]");
+    assert_golden("capabilities.json", &body);
+}
+
+#[tokio::test]
+async fn positions_match_golden() {
+    let server = TestServer::start(AppState::default()).await;
+    let response = server
+        .post(
+            "/positions",
+            json!({
+                "source": "let é = 1;\nfoo(\"😀\", x)\n",
+                "positions": [
+                    { "byte": 0 },
+                    { "utf16": { "line": 1, "character": 9 } },
+                    { "point": { "row": 0, "column": 6 } },
+                    { "utf8": { "line": 2, "character": 0 } }
+                ]
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_golden("positions.json", &response.json());
+}
+
+#[tokio::test]
+async fn invalid_positions_are_rejected_with_json_errors() {
+    let server = TestServer::start(AppState::default()).await;
+    let response = server
+        .post(
+            "/positions",
+            json!({ "source": "é", "positions": [{ "byte": 1 }] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.json(),
+        json!({ "error": "positions[0]: byte offset 1 is inside a multi-byte character" })
+    );
+}
+
+#[tokio::test]
+async fn format_is_unavailable_by_default() {
+    let server = TestServer::start(AppState::default()).await;
+    let response = server
+        .post(
+            "/format",
+            json!({ "language": "rust", "source": "fn main(){}" }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn metrics_and_diagnostics_are_served() {
+    let server = TestServer::start(AppState::default().with_admin_token("s3cret")).await;
+
+    let metrics = server.get("/metrics").await;
+    assert_eq!(metrics.status, StatusCode::OK);
+    assert!(metrics
+        .content_type
+        .as_deref()
+        .is_some_and(|value| value.starts_with("text/plain")));
+    assert!(metrics.text().contains("indexer_http_panics_total 0"));
+
+    let diagnostics = server.get_as("/admin/diagnostics", "s3cret").await;
+    assert_eq!(diagnostics.status, StatusCode::OK);
+    let body = diagnostics.json();
+    assert_eq!(body["build"]["name"], "ossaat-indexer");
+    assert_eq!(body["http_panics_total"], 0);
+}
+
+#[tokio::test]
+async fn admin_endpoints_require_the_admin_token() {
+    let disabled = TestServer::start(AppState::default()).await;
+    for path in ["/admin/diagnostics", "/admin/log-level"] {
+        assert_eq!(
+            disabled.get_as(path, "s3cret").await.status,
+            StatusCode::SERVICE_UNAVAILABLE,
+            "{path}"
+        );
+    }
+
+    let server = TestServer::start(AppState::default().with_admin_token("s3cret")).await;
+    // The token is checked before the body is parsed.
+    let invalid_body = server
+        .request(Method::PUT, "/admin/log-level", Some(json!({})), None)
+        .await;
+    assert_eq!(invalid_body.status, StatusCode::UNAUTHORIZED);
+    for path in ["/admin/diagnostics", "/admin/log-level"] {
+        assert_eq!(
+            server.get(path).await.status,
+            StatusCode::UNAUTHORIZED,
+            "{path}"
+        );
+        assert_eq!(
+            server.get_as(path, "wrong").await.status,
+            StatusCode::UNAUTHORIZED,
+            "{path}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn allow_list_rejects_loopback_when_not_listed() {
+    let state = AppState::new(
+        FormatConfig::default(),
+        AllowList::parse("10.0.0.0/8").unwrap(),
+    );
+    let server = TestServer::start(state).await;
+    let response = server.get("/healthz").await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    assert_eq!(
+        response.json(),
+        json!({ "error": "client address is not allowed" })
+    );
+}